@@ -0,0 +1,128 @@
+use super::*;
+
+struct Shelf {
+    y: u32,
+    height: u32,
+    x_cursor: u32,
+}
+
+/// A packed region within an [`Atlas`]
+///
+/// Stores both the pixel rectangle the region occupies and its normalized texture coordinates,
+/// ready to use directly as vertex UVs.
+pub struct AtlasEntry {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+    pub u0: f32,
+    pub v0: f32,
+    pub u1: f32,
+    pub v1: f32,
+}
+
+/// A dynamic texture atlas, packing many small images into one large GPU texture
+///
+/// Uses a shelf (skyline) packer: each row of inserted images forms a "shelf" of a fixed
+/// height, and new images are placed in the first shelf that is tall enough and still has room
+/// before a new shelf is opened. This is a good fit for packing many similarly-sized images, like glyphs in
+/// a font atlas, without the bookkeeping cost of a general-purpose bin packer.
+pub struct Atlas {
+    texture: Texture,
+    width: u32,
+    height: u32,
+    shelves: Vec<Shelf>,
+    y_cursor: u32,
+}
+
+impl Atlas {
+    /// Create a new, empty atlas backed by a `width` by `height` texture
+    pub fn new(ctx: &Context, width: u32, height: u32) -> Result<Atlas, GolemError> {
+        let mut texture = Texture::new(ctx)?;
+        texture.set_image(None, width, height, ColorFormat::RGBA);
+        // Packing is a stream of small set_subimage calls; regenerating the mipmap chain after
+        // every one would make filling the atlas O(inserts * texture size) instead of O(inserts).
+        texture.set_mipmaps(false);
+
+        Ok(Atlas {
+            texture,
+            width,
+            height,
+            shelves: Vec::new(),
+            y_cursor: 0,
+        })
+    }
+
+    /// The texture backing this atlas
+    pub fn texture(&self) -> &Texture {
+        &self.texture
+    }
+
+    /// Pack a `w` by `h` image into the atlas, uploading `data` and returning its placement
+    ///
+    /// Returns `None` if the region is larger than the atlas, or if there's no room left to
+    /// place it. UVs are inset by half a texel so neighboring entries don't bleed into each
+    /// other under linear filtering.
+    pub fn insert(&mut self, data: &[u8], w: u32, h: u32, color: ColorFormat) -> Option<AtlasEntry> {
+        if w > self.width || h > self.height {
+            return None;
+        }
+
+        let shelf = self
+            .shelves
+            .iter_mut()
+            .find(|shelf| shelf.height >= h && self.width - shelf.x_cursor >= w);
+
+        let (x, y) = if let Some(shelf) = shelf {
+            let x = shelf.x_cursor;
+            shelf.x_cursor += w;
+            (x, shelf.y)
+        } else {
+            if self.height - self.y_cursor < h {
+                return None;
+            }
+            let y = self.y_cursor;
+            self.y_cursor += h;
+            self.shelves.push(Shelf {
+                y,
+                height: h,
+                x_cursor: w,
+            });
+            (0, y)
+        };
+
+        self.texture.set_subimage(data, x, y, w, h, color);
+
+        let inset = 0.5;
+        let width = self.width as f32;
+        let height = self.height as f32;
+        Some(AtlasEntry {
+            x,
+            y,
+            width: w,
+            height: h,
+            u0: (x as f32 + inset) / width,
+            v0: (y as f32 + inset) / height,
+            u1: (x as f32 + w as f32 - inset) / width,
+            v1: (y as f32 + h as f32 - inset) / height,
+        })
+    }
+
+    /// Clear every packed region, freeing the whole atlas back up for new insertions
+    ///
+    /// This doesn't clear the backing texture's pixel data, only the packer's bookkeeping; the
+    /// next insertions will simply overwrite whatever was there before.
+    pub fn reset(&mut self) {
+        self.shelves.clear();
+        self.y_cursor = 0;
+    }
+
+    /// Regenerate the backing texture's mipmap chain
+    ///
+    /// `Atlas::new` disables automatic mipmap generation (see [`Texture::set_mipmaps`]) so
+    /// that packing stays cheap; call this once after a batch of insertions if the atlas is
+    /// sampled with a minification filter that needs mips.
+    pub fn generate_mipmaps(&self) {
+        self.texture.generate_mipmaps();
+    }
+}
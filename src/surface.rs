@@ -0,0 +1,202 @@
+use std::cell::Cell;
+
+use super::*;
+
+/// An offscreen render target backed by a GPU framebuffer object
+///
+/// A `Surface` lets draw calls be redirected away from the screen and into a [`Texture`],
+/// which is useful for post-processing passes, shadow maps, or any effect that needs to
+/// render an image before it's used as an input to another draw call.
+///
+/// Bind a surface with [`Surface::bind`] (or [`Context::set_surface`]) before calling
+/// [`Context::clear`] or [`Context::draw`] to target it; unbind with
+/// `ctx.set_surface(None)`, which binds the screen again. Dropping the surface instead restores
+/// the framebuffer that was bound before it; neither restores the viewport.
+pub struct Surface {
+    pub(crate) ctx: Context,
+    pub(crate) id: GlFramebuffer,
+    pub(crate) color: Texture,
+    pub(crate) depth_stencil: Option<GlRenderbuffer>,
+    previous: Cell<Option<GlFramebuffer>>,
+}
+
+impl Surface {
+    /// Create a new `Surface`, using `texture` as its color attachment
+    ///
+    /// The surface's dimensions are taken from the texture, which must already have image data
+    /// set via [`Texture::set_image`].
+    pub fn new(ctx: &Context, texture: Texture) -> Result<Surface, GolemError> {
+        Self::with_depth_stencil(ctx, texture, false)
+    }
+
+    /// Create a new `Surface`, optionally with an accompanying depth/stencil renderbuffer
+    ///
+    /// With `depth_stencil` set to `false` this is the same as [`Surface::new`]. With `true`, a
+    /// renderbuffer matching the texture's dimensions is also allocated and attached as the
+    /// depth/stencil target, which is required for depth testing to work while the surface is
+    /// bound.
+    pub fn with_depth_stencil(
+        ctx: &Context,
+        texture: Texture,
+        depth_stencil: bool,
+    ) -> Result<Surface, GolemError> {
+        assert_eq!(
+            texture.kind(),
+            TextureKind::Texture2D,
+            "Surface can only attach a 2D texture as its color attachment"
+        );
+        let ctx_ref = Context(ctx.0.clone());
+        let gl = &ctx.0.gl;
+        let previous = unsafe { gl.get_parameter_framebuffer(glow::FRAMEBUFFER_BINDING) };
+        let id = unsafe { gl.create_framebuffer()? };
+        unsafe {
+            gl.bind_framebuffer(glow::FRAMEBUFFER, Some(id));
+            gl.framebuffer_texture_2d(
+                glow::FRAMEBUFFER,
+                glow::COLOR_ATTACHMENT0,
+                glow::TEXTURE_2D,
+                Some(texture.id),
+                0,
+            );
+        }
+
+        let renderbuffer = if depth_stencil {
+            let rb = unsafe { gl.create_renderbuffer()? };
+            unsafe {
+                gl.bind_renderbuffer(glow::RENDERBUFFER, Some(rb));
+                gl.renderbuffer_storage(
+                    glow::RENDERBUFFER,
+                    glow::DEPTH24_STENCIL8,
+                    texture.width() as i32,
+                    texture.height() as i32,
+                );
+                gl.framebuffer_renderbuffer(
+                    glow::FRAMEBUFFER,
+                    glow::DEPTH_STENCIL_ATTACHMENT,
+                    glow::RENDERBUFFER,
+                    Some(rb),
+                );
+            }
+            Some(rb)
+        } else {
+            None
+        };
+
+        let status = unsafe { gl.check_framebuffer_status(glow::FRAMEBUFFER) };
+        unsafe {
+            gl.bind_framebuffer(glow::FRAMEBUFFER, previous);
+        }
+        if status != glow::FRAMEBUFFER_COMPLETE {
+            return Err(GolemError::IncompleteFramebuffer(status));
+        }
+
+        Ok(Surface {
+            ctx: ctx_ref,
+            id,
+            color: texture,
+            depth_stencil: renderbuffer,
+            previous: Cell::new(None),
+        })
+    }
+
+    /// The texture this surface renders into
+    pub fn texture(&self) -> &Texture {
+        &self.color
+    }
+
+    /// Bind this surface, redirecting subsequent `clear` and `draw` calls into its texture
+    ///
+    /// Equivalent to `ctx.set_surface(Some(surface))`.
+    pub fn bind(&self) {
+        self.ctx.set_surface(Some(self));
+    }
+
+    fn bind_and_remember(&self) {
+        let gl = &self.ctx.0.gl;
+        unsafe {
+            let previous = gl.get_parameter_framebuffer(glow::FRAMEBUFFER_BINDING);
+            self.previous.set(previous);
+
+            gl.bind_framebuffer(glow::FRAMEBUFFER, Some(self.id));
+            gl.viewport(0, 0, self.color.width() as i32, self.color.height() as i32);
+        }
+    }
+
+    /// Restore whatever framebuffer was bound before this surface, but only if this surface is
+    /// still the one currently bound
+    ///
+    /// A surface that was never bound has no `previous` to restore, and a surface that was
+    /// bound but then superseded by another surface (without unbinding first) would otherwise
+    /// clobber that other surface's binding with its own stale `previous`.
+    fn unbind(&self) {
+        let gl = &self.ctx.0.gl;
+        unsafe {
+            let current = gl.get_parameter_framebuffer(glow::FRAMEBUFFER_BINDING);
+            if current == Some(self.id) {
+                gl.bind_framebuffer(glow::FRAMEBUFFER, self.previous.take());
+            }
+        }
+    }
+}
+
+impl Drop for Surface {
+    fn drop(&mut self) {
+        self.unbind();
+        let gl = &self.ctx.0.gl;
+        unsafe {
+            if let Some(rb) = self.depth_stencil {
+                gl.delete_renderbuffer(rb);
+            }
+            gl.delete_framebuffer(self.id);
+        }
+    }
+}
+
+impl Context {
+    /// Redirect subsequent `clear` and `draw` calls into a [`Surface`], or back to the screen
+    ///
+    /// Passing `Some(surface)` binds the surface's framebuffer and resizes the viewport to
+    /// match its texture, remembering whatever framebuffer was previously bound. Passing
+    /// `None` binds the default framebuffer (the screen); to restore whatever a surface's
+    /// draws were layered on top of, let the `Surface` drop instead.
+    pub fn set_surface(&self, surface: Option<&Surface>) {
+        match surface {
+            Some(surface) => surface.bind_and_remember(),
+            None => {
+                let gl = &self.0.gl;
+                unsafe {
+                    gl.bind_framebuffer(glow::FRAMEBUFFER, None);
+                }
+            }
+        }
+    }
+
+    /// Read pixel data back from the currently-bound render target into `buffer`
+    ///
+    /// Reads a `w`×`h` rectangle starting at `(x, y)` from whatever is currently bound: the
+    /// screen, or a [`Surface`] if one was set via [`Context::set_surface`]. `GL_PACK_ALIGNMENT`
+    /// is set to 1 first so tightly-packed rows (e.g. `ColorFormat::RGB`) read back correctly.
+    /// `buffer` must be at least `w * h * color.bytes_per_pixel()` bytes long.
+    pub fn read_pixels(&self, x: u32, y: u32, w: u32, h: u32, color: ColorFormat, buffer: &mut [u8]) {
+        assert!(
+            buffer.len() >= (w * h * color.bytes_per_pixel()) as usize,
+            "The destination buffer wasn't big enough for the width, height, and format supplied"
+        );
+        let format = PixelFormat::from(color);
+        let gl = &self.0.gl;
+        unsafe {
+            let previous_alignment = gl.get_parameter_i32(glow::PACK_ALIGNMENT);
+            gl.pixel_store_i32(glow::PACK_ALIGNMENT, 1);
+            gl.read_pixels(
+                x as i32,
+                y as i32,
+                w as i32,
+                h as i32,
+                format.format.to_gl(),
+                format.data_type.to_gl(),
+                glow::PixelPackData::Slice(buffer),
+            );
+            gl.pixel_store_i32(glow::PACK_ALIGNMENT, previous_alignment);
+        }
+    }
+}
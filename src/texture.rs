@@ -5,20 +5,36 @@ use std::num::NonZeroU32;
 pub struct Texture {
     pub(crate) ctx: Context,
     pub(crate) id: GlTexture,
+    pub(crate) kind: TextureKind,
     pub(crate) width: u32,
     pub(crate) height: u32,
+    pub(crate) depth: u32,
+    generate_mipmaps: bool,
 }
 
 impl Texture {
-    /// Create a new, empty texture
+    /// Create a new, empty 2D texture
     pub fn new(ctx: &Context) -> Result<Texture, GolemError> {
+        Texture::with_kind(ctx, TextureKind::Texture2D)
+    }
+
+    /// Create a new, empty texture of the given [`TextureKind`]
+    ///
+    /// The kind determines which GL target the texture binds to (e.g. `TEXTURE_CUBE_MAP` for
+    /// [`TextureKind::Cubemap`]), which in turn determines how it must be uploaded: a 2D
+    /// texture takes a single [`Texture::set_image`] call, a cubemap takes six
+    /// [`Texture::set_cube_face`] calls, and 3D/array textures take [`Texture::set_image_3d`].
+    pub fn with_kind(ctx: &Context, kind: TextureKind) -> Result<Texture, GolemError> {
         let ctx = Context(ctx.0.clone());
         let id = unsafe { ctx.0.gl.create_texture()? };
         let tex = Texture {
             ctx,
             id,
+            kind,
             width: 0,
             height: 0,
+            depth: 0,
+            generate_mipmaps: true,
         };
         tex.set_minification(TextureFilter::Linear);
 
@@ -35,11 +51,16 @@ impl Texture {
         let gl = &self.ctx.0.gl;
         unsafe {
             gl.active_texture(glow::TEXTURE0 + bind_point.get());
-            gl.bind_texture(glow::TEXTURE_2D, Some(self.id));
+            gl.bind_texture(self.kind.to_gl(), Some(self.id));
             gl.active_texture(glow::TEXTURE0);
         }
     }
 
+    /// The kind of texture this is, and the GL target it binds to
+    pub fn kind(&self) -> TextureKind {
+        self.kind
+    }
+
     pub fn width(&self) -> u32 {
         self.width
     }
@@ -48,27 +69,100 @@ impl Texture {
         self.height
     }
 
+    /// The depth of a [`TextureKind::Texture3D`] or the layer count of a
+    /// [`TextureKind::Texture2DArray`]; 0 for every other kind
+    pub fn depth(&self) -> u32 {
+        self.depth
+    }
+
+    /// Whether uploads to this texture regenerate its mipmap chain; see [`Texture::set_mipmaps`]
+    pub fn mipmaps(&self) -> bool {
+        self.generate_mipmaps
+    }
+
+    /// Control whether [`Texture::set_image`] and [`Texture::set_subimage`] regenerate the
+    /// mipmap chain after every upload
+    ///
+    /// This defaults to `true`, which is correct for [`TextureFilter::Linear`] minification.
+    /// Pixel-art textures using [`TextureFilter::Nearest`] don't benefit from mipmapping, and
+    /// streaming updates (e.g. to an atlas) shouldn't pay to regenerate the whole chain on every
+    /// small upload; turn this off and call [`Texture::generate_mipmaps`] explicitly instead.
+    pub fn set_mipmaps(&mut self, generate: bool) {
+        self.generate_mipmaps = generate;
+    }
+
+    /// Regenerate this texture's mipmap chain immediately
+    pub fn generate_mipmaps(&self) {
+        let gl = &self.ctx.0.gl;
+        unsafe {
+            gl.bind_texture(self.kind.to_gl(), Some(self.id));
+            gl.generate_mipmap(self.kind.to_gl());
+            gl.bind_texture(self.kind.to_gl(), None);
+        }
+    }
+
+    /// Read this texture's pixel data back from the GPU into `buffer`
+    ///
+    /// `glGetTexImage` isn't available on GLES, so this works by binding the texture to a
+    /// temporary framebuffer and reading it back with [`Context::read_pixels`]. `buffer` must
+    /// be at least `width() * height() * color.bytes_per_pixel()` bytes long.
+    pub fn get_image(&self, buffer: &mut [u8], color: ColorFormat) -> Result<(), GolemError> {
+        assert_eq!(
+            self.kind,
+            TextureKind::Texture2D,
+            "get_image is only supported on 2D textures"
+        );
+        let gl = &self.ctx.0.gl;
+        let previous = unsafe { gl.get_parameter_framebuffer(glow::FRAMEBUFFER_BINDING) };
+        let fbo = unsafe { gl.create_framebuffer()? };
+        unsafe {
+            gl.bind_framebuffer(glow::FRAMEBUFFER, Some(fbo));
+            gl.framebuffer_texture_2d(
+                glow::FRAMEBUFFER,
+                glow::COLOR_ATTACHMENT0,
+                glow::TEXTURE_2D,
+                Some(self.id),
+                0,
+            );
+        }
+
+        self.ctx.read_pixels(0, 0, self.width, self.height, color, buffer);
+
+        unsafe {
+            gl.bind_framebuffer(glow::FRAMEBUFFER, previous);
+            gl.delete_framebuffer(fbo);
+        }
+        Ok(())
+    }
+
     /// Set the image data associated with this texture
     ///
     /// `width` and `height` must both be powers of 2 and less than the maximum texture size of the
     /// GPU, given by [`glow::MAX_TEXTURE_SIZE`]
     ///
+    /// `format` accepts either a [`ColorFormat`] for the common 8-bit RGB/RGBA case, or a
+    /// [`PixelFormat`] when finer control over the internal format, client layout, or data type
+    /// is needed (single-channel masks, floating-point render targets, depth textures, ...).
+    ///
     /// If 'data' is None, the image will be created with no data at the given dimensions.
     /// If it is Some, it needs to be at least as long as `width * height *
-    /// [`color.bytes_per_pixel`])
+    /// [`format.bytes_per_pixel`])
     ///
-    /// [`color.bytes_per_pixel`]: ColorFormat::bytes_per_pixel
-    pub fn set_image(&mut self, data: Option<&[u8]>, width: u32, height: u32, color: ColorFormat) {
-        assert!(width > 0, "The texture width was 0",);
-        assert!(height > 0, "The texture width was 0",);
-        assert!(
-            width < glow::MAX_TEXTURE_SIZE,
-            "The texture width was bigger than the maximum size"
-        );
-        assert!(
-            height < glow::MAX_TEXTURE_SIZE,
-            "The texture height was bigger than the maximum size"
+    /// [`format.bytes_per_pixel`]: PixelFormat::bytes_per_pixel
+    pub fn set_image(
+        &mut self,
+        data: Option<&[u8]>,
+        width: u32,
+        height: u32,
+        format: impl Into<PixelFormat>,
+    ) {
+        assert_eq!(
+            self.kind,
+            TextureKind::Texture2D,
+            "set_image is only supported on 2D textures; use set_cube_face or set_image_3d"
         );
+        let format = format.into();
+        Texture::assert_valid_dimensions(width, height);
         assert!(
             width & (width - 1) == 0,
             "The texture width was not a power of 2"
@@ -79,42 +173,148 @@ impl Texture {
         );
         if let Some(data) = data {
             assert!(
-                data.len() >= (width * height * color.bytes_per_pixel()) as usize,
+                data.len() >= (width * height * format.bytes_per_pixel()) as usize,
                 "The texture data wasn't big enough for the width, height, and format supplied"
             );
         }
         self.width = width;
         self.height = height;
 
-        let format = match color {
-            ColorFormat::RGB => glow::RGB,
-            ColorFormat::RGBA => glow::RGBA,
-        };
         let gl = &self.ctx.0.gl;
         unsafe {
             gl.bind_texture(glow::TEXTURE_2D, Some(self.id));
             gl.tex_image_2d(
                 glow::TEXTURE_2D,
                 0,
-                format as i32,
+                format.internal.to_gl() as i32,
                 width as i32,
                 height as i32,
                 0,
-                format,
-                glow::UNSIGNED_BYTE,
+                format.format.to_gl(),
+                format.data_type.to_gl(),
                 data,
             );
-            gl.generate_mipmap(glow::TEXTURE_2D);
+            if self.generate_mipmaps {
+                gl.generate_mipmap(glow::TEXTURE_2D);
+            }
             gl.bind_texture(glow::TEXTURE_2D, None);
         }
     }
 
+    /// Upload one face of a [`TextureKind::Cubemap`] texture
+    ///
+    /// Behaves like [`Texture::set_image`], but targets a single `face` of the cube; call this
+    /// six times, once per face, to fully populate the cubemap.
+    pub fn set_cube_face(
+        &mut self,
+        face: Face,
+        data: Option<&[u8]>,
+        width: u32,
+        height: u32,
+        format: impl Into<PixelFormat>,
+    ) {
+        assert_eq!(
+            self.kind,
+            TextureKind::Cubemap,
+            "set_cube_face is only supported on cubemap textures"
+        );
+        let format = format.into();
+        Texture::assert_valid_dimensions(width, height);
+        if self.width > 0 || self.height > 0 {
+            assert!(
+                width == self.width && height == self.height,
+                "Every face of a cubemap must share the same dimensions"
+            );
+        }
+        if let Some(data) = data {
+            assert!(
+                data.len() >= (width * height * format.bytes_per_pixel()) as usize,
+                "The texture data wasn't big enough for the width, height, and format supplied"
+            );
+        }
+        self.width = width;
+        self.height = height;
+
+        let gl = &self.ctx.0.gl;
+        unsafe {
+            gl.bind_texture(glow::TEXTURE_CUBE_MAP, Some(self.id));
+            gl.tex_image_2d(
+                face.to_gl(),
+                0,
+                format.internal.to_gl() as i32,
+                width as i32,
+                height as i32,
+                0,
+                format.format.to_gl(),
+                format.data_type.to_gl(),
+                data,
+            );
+            if self.generate_mipmaps {
+                gl.generate_mipmap(glow::TEXTURE_CUBE_MAP);
+            }
+            gl.bind_texture(glow::TEXTURE_CUBE_MAP, None);
+        }
+    }
+
+    /// Upload image data for a [`TextureKind::Texture3D`] or [`TextureKind::Texture2DArray`]
+    /// texture
+    ///
+    /// `depth` is the number of voxel slices for a 3D texture, or the number of layers for a
+    /// texture array.
+    pub fn set_image_3d(
+        &mut self,
+        data: Option<&[u8]>,
+        width: u32,
+        height: u32,
+        depth: u32,
+        format: impl Into<PixelFormat>,
+    ) {
+        assert!(
+            self.kind == TextureKind::Texture3D || self.kind == TextureKind::Texture2DArray,
+            "set_image_3d is only supported on 3D or array textures"
+        );
+        let format = format.into();
+        Texture::assert_valid_dimensions(width, height);
+        if let Some(data) = data {
+            assert!(
+                data.len() >= (width * height * depth * format.bytes_per_pixel()) as usize,
+                "The texture data wasn't big enough for the width, height, depth, and format supplied"
+            );
+        }
+        self.width = width;
+        self.height = height;
+        self.depth = depth;
+
+        let target = self.kind.to_gl();
+        let gl = &self.ctx.0.gl;
+        unsafe {
+            gl.bind_texture(target, Some(self.id));
+            gl.tex_image_3d(
+                target,
+                0,
+                format.internal.to_gl() as i32,
+                width as i32,
+                height as i32,
+                depth as i32,
+                0,
+                format.format.to_gl(),
+                format.data_type.to_gl(),
+                data,
+            );
+            if self.generate_mipmaps {
+                gl.generate_mipmap(target);
+            }
+            gl.bind_texture(target, None);
+        }
+    }
+
     /// Set a region of the texture data
     ///
-    /// The data provided must be enough to cover `(width - x) * (height - y) *
-    /// [`color.bytes_per_pixel()`]`. Also, the region must be within the texture's bounds.
+    /// `data` is assumed to be tightly packed (`width` pixels per row); use
+    /// [`Texture::set_subimage_stride`] if it's padded out to a wider row. The region must be
+    /// within the texture's bounds.
     ///
-    /// [`color.bytes_per_pixel()`]: ColorFormat::bytes_per_pixel
+    /// See [`Texture::set_image`] for the meaning of `format`.
     pub fn set_subimage(
         &self,
         data: &[u8],
@@ -122,8 +322,38 @@ impl Texture {
         y: u32,
         width: u32,
         height: u32,
-        color: ColorFormat,
+        format: impl Into<PixelFormat>,
+    ) {
+        self.set_subimage_stride(data, x, y, width, height, format, None);
+    }
+
+    /// Set a region of the texture data, reading source rows with a custom pixel stride
+    ///
+    /// `stride` is the number of pixels between the start of one source row and the next; pass
+    /// `None` to treat `data` as tightly packed (`stride == width`). This is useful when `data`
+    /// is a view into a larger image and its rows aren't contiguous with `width` pixels of this
+    /// region.
+    ///
+    /// `data` must be at least `stride.unwrap_or(width) * (height - 1) * bytes_per_pixel +
+    /// width * bytes_per_pixel` long, enough to cover every row up to and including the last one
+    /// without reading past the end of the final row. Also, the region must be within the
+    /// texture's bounds.
+    pub fn set_subimage_stride(
+        &self,
+        data: &[u8],
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+        format: impl Into<PixelFormat>,
+        stride: Option<u32>,
     ) {
+        assert_eq!(
+            self.kind,
+            TextureKind::Texture2D,
+            "set_subimage is only supported on 2D textures"
+        );
+        let format = format.into();
         assert!(
             x + width <= self.width,
             "The region over-ran the width of the texture"
@@ -132,15 +362,23 @@ impl Texture {
             y + height <= self.height,
             "The region over-ran the height of the texture"
         );
-        let format = match color {
-            ColorFormat::RGB => glow::RGB,
-            ColorFormat::RGBA => glow::RGBA,
-        };
-        let required_data_len = (width - x) * (height - y) * color.bytes_per_pixel();
-        assert!(data.len() >= required_data_len as usize);
+        let stride = stride.unwrap_or(width);
+        assert!(
+            stride >= width,
+            "The row stride can't be smaller than the region's width"
+        );
+        let bpp = format.bytes_per_pixel();
+        let required_data_len = stride * (height - 1) * bpp + width * bpp;
+        assert!(
+            data.len() >= required_data_len as usize,
+            "The texture data wasn't big enough for the width, height, stride, and format supplied"
+        );
         let gl = &self.ctx.0.gl;
         unsafe {
             gl.bind_texture(glow::TEXTURE_2D, Some(self.id));
+            let previous_alignment = gl.get_parameter_i32(glow::UNPACK_ALIGNMENT);
+            gl.pixel_store_i32(glow::UNPACK_ROW_LENGTH, stride as i32);
+            gl.pixel_store_i32(glow::UNPACK_ALIGNMENT, 1);
             gl.tex_sub_image_2d_u8_slice(
                 glow::TEXTURE_2D,
                 0,
@@ -148,20 +386,41 @@ impl Texture {
                 y as i32,
                 width as i32,
                 height as i32,
-                format,
-                glow::UNSIGNED_BYTE,
+                format.format.to_gl(),
+                format.data_type.to_gl(),
                 Some(data),
             );
-            gl.generate_mipmap(glow::TEXTURE_2D);
+            gl.pixel_store_i32(glow::UNPACK_ROW_LENGTH, 0);
+            gl.pixel_store_i32(glow::UNPACK_ALIGNMENT, previous_alignment);
+            if self.generate_mipmaps {
+                gl.generate_mipmap(glow::TEXTURE_2D);
+            }
             gl.bind_texture(glow::TEXTURE_2D, None);
         }
     }
 
+    /// Check that a texture upload's width/height are non-zero and within `MAX_TEXTURE_SIZE`
+    ///
+    /// Shared by [`Texture::set_image`], [`Texture::set_cube_face`], and
+    /// [`Texture::set_image_3d`]; power-of-2 checks are layered on top where they apply.
+    fn assert_valid_dimensions(width: u32, height: u32) {
+        assert!(width > 0, "The texture width was 0");
+        assert!(height > 0, "The texture height was 0");
+        assert!(
+            width < glow::MAX_TEXTURE_SIZE,
+            "The texture width was bigger than the maximum size"
+        );
+        assert!(
+            height < glow::MAX_TEXTURE_SIZE,
+            "The texture height was bigger than the maximum size"
+        );
+    }
+
     fn set_texture_param(&self, param: u32, value: i32) {
         let gl = &self.ctx.0.gl;
         unsafe {
-            gl.bind_texture(glow::TEXTURE_2D, Some(self.id));
-            gl.tex_parameter_i32(glow::TEXTURE_2D, param, value);
+            gl.bind_texture(self.kind.to_gl(), Some(self.id));
+            gl.tex_parameter_i32(self.kind.to_gl(), param, value);
         }
     }
 
@@ -227,6 +486,209 @@ impl TextureWrap {
     }
 }
 
+/// What dimensionality and layering a [`Texture`] has, and the GL target it binds to
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum TextureKind {
+    /// A standard two-dimensional image, the default for [`Texture::new`]
+    Texture2D,
+    /// Six square faces forming the inside of a cube, addressed by direction rather than UV;
+    /// used for skyboxes and reflection probes. Upload each face with [`Texture::set_cube_face`]
+    Cubemap,
+    /// A three-dimensional grid of voxels, used for volumetric data
+    Texture3D,
+    /// A fixed-size array of same-sized 2D layers, sampled with a layer index alongside the UV
+    Texture2DArray,
+}
+
+impl TextureKind {
+    pub(crate) fn to_gl(self) -> u32 {
+        match self {
+            TextureKind::Texture2D => glow::TEXTURE_2D,
+            TextureKind::Cubemap => glow::TEXTURE_CUBE_MAP,
+            TextureKind::Texture3D => glow::TEXTURE_3D,
+            TextureKind::Texture2DArray => glow::TEXTURE_2D_ARRAY,
+        }
+    }
+}
+
+/// One face of a [`TextureKind::Cubemap`] texture, named by the axis direction it faces
+#[derive(PartialEq, Eq, Clone, Copy)]
+pub enum Face {
+    PositiveX,
+    NegativeX,
+    PositiveY,
+    NegativeY,
+    PositiveZ,
+    NegativeZ,
+}
+
+impl Face {
+    pub(crate) fn to_gl(self) -> u32 {
+        match self {
+            Face::PositiveX => glow::TEXTURE_CUBE_MAP_POSITIVE_X,
+            Face::NegativeX => glow::TEXTURE_CUBE_MAP_NEGATIVE_X,
+            Face::PositiveY => glow::TEXTURE_CUBE_MAP_POSITIVE_Y,
+            Face::NegativeY => glow::TEXTURE_CUBE_MAP_NEGATIVE_Y,
+            Face::PositiveZ => glow::TEXTURE_CUBE_MAP_POSITIVE_Z,
+            Face::NegativeZ => glow::TEXTURE_CUBE_MAP_NEGATIVE_Z,
+        }
+    }
+}
+
+/// The sized format a texture's image data is stored in on the GPU
+pub enum TextureInternalFormat {
+    /// A single 8-bit channel
+    R8,
+    /// Two 8-bit channels
+    RG8,
+    /// Three 8-bit channels
+    RGB8,
+    /// Four 8-bit channels
+    RGBA8,
+    /// A single 16-bit floating-point channel
+    R16F,
+    /// Four 16-bit floating-point channels
+    RGBA16F,
+    /// A 24-bit depth channel, for use as a depth attachment
+    Depth24,
+}
+
+impl TextureInternalFormat {
+    pub(crate) fn to_gl(self) -> u32 {
+        match self {
+            TextureInternalFormat::R8 => glow::R8,
+            TextureInternalFormat::RG8 => glow::RG8,
+            TextureInternalFormat::RGB8 => glow::RGB8,
+            TextureInternalFormat::RGBA8 => glow::RGBA8,
+            TextureInternalFormat::R16F => glow::R16F,
+            TextureInternalFormat::RGBA16F => glow::RGBA16F,
+            TextureInternalFormat::Depth24 => glow::DEPTH_COMPONENT24,
+        }
+    }
+}
+
+/// The client-side layout of the pixel data passed to a texture upload
+pub enum TextureFormat {
+    /// A single channel, uploaded as the texture's red component
+    R,
+    /// Two channels, uploaded as the texture's red and green components
+    RG,
+    /// Three channels, in red-green-blue order
+    RGB,
+    /// Four channels, in red-green-blue-alpha order
+    RGBA,
+    /// Four channels, in blue-green-red-alpha order
+    BGRA,
+    /// A single depth channel; the only valid client format for [`TextureInternalFormat::Depth24`]
+    DepthComponent,
+}
+
+impl TextureFormat {
+    pub(crate) fn channels(&self) -> u32 {
+        match self {
+            TextureFormat::R => 1,
+            TextureFormat::RG => 2,
+            TextureFormat::RGB => 3,
+            TextureFormat::RGBA => 4,
+            TextureFormat::BGRA => 4,
+            TextureFormat::DepthComponent => 1,
+        }
+    }
+
+    pub(crate) fn to_gl(&self) -> u32 {
+        match self {
+            TextureFormat::R => glow::RED,
+            TextureFormat::RG => glow::RG,
+            TextureFormat::RGB => glow::RGB,
+            TextureFormat::RGBA => glow::RGBA,
+            TextureFormat::BGRA => glow::BGRA,
+            TextureFormat::DepthComponent => glow::DEPTH_COMPONENT,
+        }
+    }
+}
+
+/// The numeric type of each channel in the pixel data passed to a texture upload
+pub enum TextureDataType {
+    /// An 8-bit unsigned integer per channel
+    U8,
+    /// A 16-bit unsigned integer per channel
+    U16,
+    /// A 32-bit floating point number per channel
+    F32,
+}
+
+impl TextureDataType {
+    pub(crate) fn bytes(&self) -> u32 {
+        match self {
+            TextureDataType::U8 => 1,
+            TextureDataType::U16 => 2,
+            TextureDataType::F32 => 4,
+        }
+    }
+
+    pub(crate) fn to_gl(&self) -> u32 {
+        match self {
+            TextureDataType::U8 => glow::UNSIGNED_BYTE,
+            TextureDataType::U16 => glow::UNSIGNED_SHORT,
+            TextureDataType::F32 => glow::FLOAT,
+        }
+    }
+}
+
+/// The full description of how a texture's pixel data is stored and uploaded
+///
+/// Combines an internal (GPU-side) format with the client-side layout and data type of the
+/// pixels passed to [`Texture::set_image`]/[`Texture::set_subimage`]. For the common case of
+/// 8-bit RGB/RGBA data, use a [`ColorFormat`] instead, which converts into a `PixelFormat`.
+pub struct PixelFormat {
+    pub internal: TextureInternalFormat,
+    pub format: TextureFormat,
+    pub data_type: TextureDataType,
+}
+
+impl PixelFormat {
+    pub(crate) fn bytes_per_pixel(&self) -> u32 {
+        self.format.channels() * self.data_type.bytes()
+    }
+}
+
+/// A convenience format for the common case of 8-bit-per-channel RGB or RGBA image data
+pub enum ColorFormat {
+    RGB,
+    RGBA,
+}
+
+impl ColorFormat {
+    pub(crate) fn bytes_per_pixel(&self) -> u32 {
+        PixelFormat::from(*self).bytes_per_pixel()
+    }
+}
+
+impl Clone for ColorFormat {
+    fn clone(&self) -> Self {
+        match self {
+            ColorFormat::RGB => ColorFormat::RGB,
+            ColorFormat::RGBA => ColorFormat::RGBA,
+        }
+    }
+}
+
+impl Copy for ColorFormat {}
+
+impl From<ColorFormat> for PixelFormat {
+    fn from(color: ColorFormat) -> Self {
+        let (internal, format) = match color {
+            ColorFormat::RGB => (TextureInternalFormat::RGB8, TextureFormat::RGB),
+            ColorFormat::RGBA => (TextureInternalFormat::RGBA8, TextureFormat::RGBA),
+        };
+        PixelFormat {
+            internal,
+            format,
+            data_type: TextureDataType::U8,
+        }
+    }
+}
+
 impl Drop for Texture {
     fn drop(&mut self) {
         unsafe {